@@ -0,0 +1,91 @@
+use std::env;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+struct Operation {
+    name: String,
+    raw_value: u8,
+    kind: String,
+    handler: String,
+}
+
+fn parse_instructions(source: &str) -> Vec<Operation> {
+    let mut operations = Vec::new();
+
+    for line in source.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let columns: Vec<&str> = line.split(',').map(|c| c.trim()).collect();
+        assert_eq!(columns.len(), 4, "malformed instructions.in line: {}", line);
+
+        operations.push(Operation {
+            name: columns[0].to_string(),
+            raw_value: columns[1].parse().expect("raw opcode value must be a u8"),
+            kind: columns[2].to_string(),
+            handler: columns[3].to_string(),
+        });
+    }
+
+    operations
+}
+
+fn generate(operations: &[Operation]) -> String {
+    let mut out = String::new();
+
+    out.push_str("// @generated by build.rs from instructions.in. Do not edit by hand.\n\n");
+
+    out.push_str("#[repr(u8)]\n#[derive(Debug, Clone, Copy, PartialEq, Eq)]\npub enum OperationType {\n");
+    for op in operations {
+        let _ = writeln!(out, "    {} = {},", op.name, op.raw_value);
+    }
+    out.push_str("}\n\n");
+
+    for op in operations {
+        let _ = writeln!(
+            out,
+            "pub const {}_OPERATION_RAW_VALUE: u8 = OperationType::{} as u8;",
+            op.name, op.name
+        );
+    }
+    out.push('\n');
+
+    out.push_str("pub fn decode_operation_type(op_u8: u8) -> Option<OperationType> {\n    match op_u8 {\n");
+    for op in operations {
+        let _ = writeln!(out, "        {}_OPERATION_RAW_VALUE => Some(OperationType::{}),", op.name, op.name);
+    }
+    out.push_str("        _ => None,\n    }\n}\n\n");
+
+    out.push_str("fn dispatch_operation(operation: OperationType, input: &[u8]) -> Result<Vec<u8>, ApiError> {\n    match operation {\n");
+    for op in operations {
+        match op.kind.as_str() {
+            "simple" => {
+                let _ = writeln!(out, "        OperationType::{} => {}(&input),", op.name, op.handler);
+            },
+            "pairing" => {
+                let _ = writeln!(out, "        OperationType::{} => pairing_operation!(input, {}),", op.name, op.handler);
+            },
+            other => panic!("unknown dispatch kind `{}` for operation `{}`", other, op.name),
+        }
+    }
+    out.push_str("    }\n}\n");
+
+    out
+}
+
+fn main() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let instructions_path = Path::new(&manifest_dir).join("instructions.in");
+    println!("cargo:rerun-if-changed={}", instructions_path.display());
+
+    let source = fs::read_to_string(&instructions_path).expect("failed to read instructions.in");
+    let operations = parse_instructions(&source);
+    let generated = generate(&operations);
+
+    let out_dir = env::var("OUT_DIR").unwrap();
+    let dest_path = Path::new(&out_dir).join("operations_generated.rs");
+    fs::write(&dest_path, generated).expect("failed to write generated operations table");
+}
@@ -0,0 +1,237 @@
+// C ABI surface for `eip1962`. Kept as its own crate (rather than living in
+// `eip1962` itself) so the core crate can stay `rlib`-only and `no_std`
+// capable: `staticlib`/`cdylib` are final-link artifacts that need a
+// `#[panic_handler]`/`#[global_allocator]` resolved at build time, which this
+// crate gets for free by always depending on `eip1962` with `std` enabled.
+
+use eip1962::errors::ApiError;
+use eip1962::public_interface::unified_api::{decode_operation_type, perform_operation};
+
+// For C style API caller has to preallocate some buffers for results
+pub const PREALLOCATE_FOR_ERROR_BYTES: usize = 256;
+pub const PREALLOCATE_FOR_RESULT_BYTES: usize = 768;
+
+use static_assertions::const_assert;
+const_assert!(PREALLOCATE_FOR_RESULT_BYTES == eip1962::public_interface::constants::MAX_MODULUS_BYTE_LEN * 3 * 2);
+
+// Stable numeric error taxonomy for the C ABI. `c_perform_operation` used to only
+// signal failure with a bare `1u32` and a free-form string, which left callers
+// (e.g. an EVM precompile host) no way to branch on the failure reason other than
+// parsing `error.description()` text. These codes are part of the ABI: existing
+// variants must keep their values, new ones are appended at the end.
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApiErrorCode {
+    Success = 0,
+    UnknownOperation = 1,
+    InputTooShort = 2,
+    InvalidModulus = 3,
+    PointNotOnCurve = 4,
+    SubgroupCheckFailed = 5,
+    UnexpectedTrailingBytes = 6,
+    OutputBufferTooSmall = 7,
+    Other = 255,
+}
+
+// Maps an `ApiError` onto the stable taxonomy above. `ApiError` itself stays a
+// free-form, human-readable error (it's also used for the pure-Rust
+// `perform_operation` API, where callers can match on it directly), so this
+// only needs to give FFI callers a coarse, stable signal.
+fn classify_error(error: &ApiError) -> ApiErrorCode {
+    match error {
+        ApiError::InputIsTooShort(_) => ApiErrorCode::InputTooShort,
+        ApiError::InvalidModulus(_) => ApiErrorCode::InvalidModulus,
+        ApiError::PointIsNotOnCurve(_) => ApiErrorCode::PointNotOnCurve,
+        ApiError::SubgroupCheckFailed(_) => ApiErrorCode::SubgroupCheckFailed,
+        ApiError::UnexpectedTrailingData(_) => ApiErrorCode::UnexpectedTrailingBytes,
+        ApiError::InputError(_) => ApiErrorCode::Other,
+    }
+}
+
+// Fills `dst` with as much of `src` as fits and returns the number of bytes written.
+fn fill_buffer(dst: &mut [u8], src: &[u8]) -> usize {
+    let to_copy = core::cmp::min(dst.len(), src.len());
+    dst[..to_copy].copy_from_slice(&src[..to_copy]);
+
+    to_copy
+}
+
+// this is C interface
+#[no_mangle]
+pub extern "C" fn c_perform_operation(
+    op: ::core::ffi::c_char,
+    i: *const ::core::ffi::c_char,
+    i_len: u32,
+    o: *mut ::core::ffi::c_char,
+    o_len: *mut u32,
+    err: *mut ::core::ffi::c_char,
+    char_len: *mut u32) -> u32
+{
+    let op_u8: u8 = unsafe { core::mem::transmute(op) };
+    let err_out_i8: &mut [i8] = unsafe { core::slice::from_raw_parts_mut(err, PREALLOCATE_FOR_ERROR_BYTES) };
+    let mut err_out: &mut [u8] = unsafe { core::mem::transmute(err_out_i8) };
+
+    let operation = match decode_operation_type(op_u8) {
+        Some(operation) => operation,
+        None => {
+            let bytes_written = fill_buffer(&mut err_out, b"Unknown operation type\0");
+            unsafe { *char_len = bytes_written as u32 };
+
+            return ApiErrorCode::UnknownOperation as u32;
+        }
+    };
+
+    let input_i8: & [i8] = unsafe { core::slice::from_raw_parts(i, i_len as usize) };
+    let input: &[u8] = unsafe { core::mem::transmute(input_i8) };
+
+    let raw_out_i8: &mut [i8] = unsafe { core::slice::from_raw_parts_mut(o, PREALLOCATE_FOR_RESULT_BYTES) };
+    let mut raw_out: &mut [u8] = unsafe { core::mem::transmute(raw_out_i8) };
+
+    let result = perform_operation(operation, input);
+
+    match result {
+        Ok(result) => {
+            if result.len() > raw_out.len() {
+                let bytes_written = fill_buffer(&mut err_out, b"Output buffer is too small\0");
+                unsafe { *char_len = bytes_written as u32 };
+
+                return ApiErrorCode::OutputBufferTooSmall as u32;
+            }
+
+            let bytes_written = fill_buffer(&mut raw_out, result.as_ref());
+            unsafe { *o_len = bytes_written as u32 };
+
+            ApiErrorCode::Success as u32
+        },
+        Err(error) => {
+            // The secondary, human-readable description is best-effort: callers that
+            // only care about branching on failure reason can ignore it entirely.
+            let err_description = error.to_string();
+            let bytes_written = fill_buffer(&mut err_out, err_description.as_bytes());
+            unsafe { *char_len = bytes_written as u32 };
+
+            classify_error(&error) as u32
+        }
+    }
+}
+
+// Descriptor for one operation inside a batch submitted through `c_perform_operation_batch`.
+#[repr(C)]
+pub struct BatchOperationDescriptor {
+    pub op: u8,
+    pub input: *const ::core::ffi::c_char,
+    pub input_len: u32,
+}
+
+// Batched counterpart of `c_perform_operation`, for callers that want to run many
+// operations (e.g. benchmarking precompile gas cost, validating a batch of proofs)
+// without paying the FFI-call overhead per item. Every item gets its own output
+// slot of `o_stride` bytes starting at `o + i * o_stride`, its own length in
+// `o_lens[i]` and its own `ApiErrorCode` in `error_codes[i]`, so one bad item
+// doesn't abort the rest of the batch. Returns the number of failed items.
+#[no_mangle]
+pub extern "C" fn c_perform_operation_batch(
+    ops: *const BatchOperationDescriptor,
+    num_ops: u32,
+    o: *mut ::core::ffi::c_char,
+    o_stride: u32,
+    o_lens: *mut u32,
+    error_codes: *mut u32) -> u32
+{
+    let descriptors: &[BatchOperationDescriptor] = unsafe { core::slice::from_raw_parts(ops, num_ops as usize) };
+    let o_lens: &mut [u32] = unsafe { core::slice::from_raw_parts_mut(o_lens, num_ops as usize) };
+    let error_codes: &mut [u32] = unsafe { core::slice::from_raw_parts_mut(error_codes, num_ops as usize) };
+
+    let mut num_failed = 0u32;
+
+    for (i, descriptor) in descriptors.iter().enumerate() {
+        let operation = match decode_operation_type(descriptor.op) {
+            Some(operation) => operation,
+            None => {
+                o_lens[i] = 0;
+                error_codes[i] = ApiErrorCode::UnknownOperation as u32;
+                num_failed += 1;
+
+                continue;
+            }
+        };
+
+        let input_i8: &[i8] = unsafe { core::slice::from_raw_parts(descriptor.input, descriptor.input_len as usize) };
+        let input: &[u8] = unsafe { core::mem::transmute(input_i8) };
+
+        let item_out_i8: &mut [i8] = unsafe {
+            core::slice::from_raw_parts_mut(o.add(i * o_stride as usize), o_stride as usize)
+        };
+        let mut item_out: &mut [u8] = unsafe { core::mem::transmute(item_out_i8) };
+
+        match perform_operation(operation, input) {
+            Ok(result) => {
+                if result.len() > item_out.len() {
+                    o_lens[i] = 0;
+                    error_codes[i] = ApiErrorCode::OutputBufferTooSmall as u32;
+                    num_failed += 1;
+
+                    continue;
+                }
+
+                let bytes_written = fill_buffer(&mut item_out, result.as_ref());
+                o_lens[i] = bytes_written as u32;
+                error_codes[i] = ApiErrorCode::Success as u32;
+            },
+            Err(error) => {
+                o_lens[i] = 0;
+                error_codes[i] = classify_error(&error) as u32;
+                num_failed += 1;
+            }
+        }
+    }
+
+    num_failed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fill_buffer_copies_when_dst_is_large_enough() {
+        let mut dst = [0u8; 8];
+        let written = fill_buffer(&mut dst, b"hello");
+
+        assert_eq!(written, 5);
+        assert_eq!(&dst[..5], b"hello");
+    }
+
+    #[test]
+    fn fill_buffer_truncates_when_dst_is_too_small() {
+        let mut dst = [0u8; 3];
+        let written = fill_buffer(&mut dst, b"hello");
+
+        assert_eq!(written, 3);
+        assert_eq!(&dst, b"hel");
+    }
+
+    #[test]
+    fn fill_buffer_handles_empty_dst() {
+        let mut dst: [u8; 0] = [];
+        let written = fill_buffer(&mut dst, b"hello");
+
+        assert_eq!(written, 0);
+    }
+
+    #[test]
+    fn classify_error_maps_every_variant_to_its_stable_code() {
+        let cases = [
+            (ApiError::InputIsTooShort("x".into()), ApiErrorCode::InputTooShort),
+            (ApiError::InvalidModulus("x".into()), ApiErrorCode::InvalidModulus),
+            (ApiError::PointIsNotOnCurve("x".into()), ApiErrorCode::PointNotOnCurve),
+            (ApiError::SubgroupCheckFailed("x".into()), ApiErrorCode::SubgroupCheckFailed),
+            (ApiError::UnexpectedTrailingData("x".into()), ApiErrorCode::UnexpectedTrailingBytes),
+            (ApiError::InputError("x".into()), ApiErrorCode::Other),
+        ];
+
+        for (error, expected_code) in cases.iter() {
+            assert_eq!(classify_error(error), *expected_code);
+        }
+    }
+}
@@ -0,0 +1,34 @@
+use core::fmt;
+
+#[cfg(not(feature = "std"))]
+use alloc::string::String;
+
+// Variants are deliberately coarse categories of "why did decoding/arithmetic
+// fail", not one-variant-per-check: `ApiErrorCode` in the `ffi` crate
+// (`ffi/src/lib.rs`) is the place that maps these onto stable numeric codes
+// for FFI callers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ApiError {
+    InputIsTooShort(String),
+    InvalidModulus(String),
+    PointIsNotOnCurve(String),
+    SubgroupCheckFailed(String),
+    UnexpectedTrailingData(String),
+    InputError(String),
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiError::InputIsTooShort(descr) => write!(f, "Input is too short: {}", descr),
+            ApiError::InvalidModulus(descr) => write!(f, "Invalid modulus: {}", descr),
+            ApiError::PointIsNotOnCurve(descr) => write!(f, "Point is not on curve: {}", descr),
+            ApiError::SubgroupCheckFailed(descr) => write!(f, "Subgroup check failed: {}", descr),
+            ApiError::UnexpectedTrailingData(descr) => write!(f, "Unexpected trailing data: {}", descr),
+            ApiError::InputError(descr) => write!(f, "Invalid input: {}", descr),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ApiError {}
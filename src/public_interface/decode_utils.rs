@@ -0,0 +1,29 @@
+use crate::errors::ApiError;
+
+// A G1 curve is encoded, right after the modulus, as its `A` and `B`
+// Weierstrass coefficients — base-field (`Fp`) elements, `modulus_byte_len`
+// bytes each — followed by the group order as a length-prefixed field. G2
+// coefficients live in an extension field and are a different, wider shape,
+// so this does NOT generalize to G2; there is deliberately no G2 version of
+// this function. Used only by the cheap, parse-only `decode_operation` path
+// to re-derive the G1 multiexp pair count without the expensive point
+// decoding the real `PublicG1Api` decoder does — it is not shared with it,
+// so it's a best-effort parse that can drift if that layout ever changes.
+// Returns the group order's encoded byte length alongside the remaining
+// bytes, since callers need it to size what follows the order (e.g. a
+// multiexp scalar is encoded to that same width).
+pub fn skip_curve_coefficients_and_order(modulus_byte_len: usize, rest_after_modulus: &[u8]) -> Result<(usize, &[u8]), ApiError> {
+    let curve_coefficients_len = 2 * modulus_byte_len;
+    if rest_after_modulus.len() <= curve_coefficients_len {
+        return Err(ApiError::InputIsTooShort("not enough bytes for curve coefficients".into()));
+    }
+
+    let after_curve_coefficients = &rest_after_modulus[curve_coefficients_len..];
+    let order_byte_len = after_curve_coefficients[0] as usize;
+    let after_order_len_byte = 1 + order_byte_len;
+    if after_curve_coefficients.len() < after_order_len_byte {
+        return Err(ApiError::InputIsTooShort("not enough bytes for the group order".into()));
+    }
+
+    Ok((order_byte_len, &after_curve_coefficients[after_order_len_byte..]))
+}
@@ -0,0 +1,2 @@
+pub mod decode_utils;
+pub mod unified_api;
@@ -4,199 +4,239 @@ use crate::public_interface::g2_ops::{G2Api, PublicG2Api};
 
 use crate::errors::ApiError;
 
-// For C style API caller has to preallocate some buffers for results 
-pub const PREALLOCATE_FOR_ERROR_BYTES: usize = 256;
-pub const PREALLOCATE_FOR_RESULT_BYTES: usize = 768;
-
-use static_assertions::const_assert;
-const_assert!(PREALLOCATE_FOR_RESULT_BYTES == crate::public_interface::constants::MAX_MODULUS_BYTE_LEN * 3 * 2);
-
-#[repr(u8)]
-pub enum OperationType {
-    G1ADD = 1,
-    G1MUL = 2,
-    G1MULTIEXP = 3,
-    G2ADD = 4,
-    G2MUL = 5,
-    G2MULTIEXP = 6,
-    BLS12PAIR = 7,
-    BNPAIR = 8,
-    MNT4PAIR = 9,
-    MNT6PAIR = 10,
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+// Decodes the modulus front matter every operation's input starts with: modulus
+// byte length, the modulus's own bit length, the modulus itself, the number of
+// limbs it needs, and the remaining bytes that follow the modulus. Shared by
+// `pairing_operation!` (to pick a limb-width specialization) and
+// `decode_operation` (to report it, and go on decoding curve parameters,
+// without running the specialization).
+//
+// `modulus_byte_len` and the modulus's bit length are deliberately both
+// threaded through rather than just the byte length: a modulus can use as few
+// as one bit of its top byte (e.g. a 249-bit modulus still has a 32-byte
+// encoding), so the byte length alone can overstate the true bit length by up
+// to 7 bits. Callers that care about security level (e.g. comparing against a
+// target curve's bit size) need the bit length, not the byte length.
+fn decode_modulus_front_matter(input: &[u8]) -> Result<(usize, usize, usize, &[u8]), ApiError> {
+    use crate::field::*;
+
+    let (modulus_byte_len, modulus, rest) = parse_modulus_and_length(input)?;
+    let modulus_bit_length = modulus.bits();
+    let modulus_limbs = num_limbs_for_modulus(&modulus)?;
+
+    Ok((modulus_byte_len, modulus_bit_length, modulus_limbs, rest))
 }
 
-pub const G1ADD_OPERATION_RAW_VALUE: u8 = OperationType::G1ADD as u8;
-pub const G1MUL_OPERATION_RAW_VALUE: u8 = OperationType::G1MUL as u8;
-pub const G1MULTIEXP_OPERATION_RAW_VALUE: u8 = OperationType::G1MULTIEXP as u8;
+// Shared preamble for every pairing operation: figure out the modulus limb count
+// from the input, then hand off to the limb-width-specialized implementation.
+// Kept as a macro (rather than inlined per-variant) so `build.rs` only needs to
+// emit the `pair_*` tag for each pairing row of `instructions.in`.
+macro_rules! pairing_operation {
+    ($input:expr, $pairing_fn:ident) => {{
+        let (_, _, modulus_limbs, _) = decode_modulus_front_matter(&$input)?;
 
-pub const G2ADD_OPERATION_RAW_VALUE: u8 = OperationType::G2ADD as u8;
-pub const G2MUL_OPERATION_RAW_VALUE: u8 = OperationType::G2MUL as u8;
-pub const G2MULTIEXP_OPERATION_RAW_VALUE: u8 = OperationType::G2MULTIEXP as u8;
+        let result: Result<Vec<u8>, ApiError> = expand_for_modulus_limbs!(modulus_limbs, PairingApiImplementation, $input, $pairing_fn);
 
-pub const BLS12PAIR_OPERATION_RAW_VALUE: u8 = OperationType::BLS12PAIR as u8;
-pub const BNPAI_OPERATION_RAW_VALUE: u8 = OperationType::BNPAIR as u8;
-pub const MNT4PAIR_OPERATION_RAW_VALUE: u8 = OperationType::MNT4PAIR as u8;
-pub const MNT6PAIR_OPERATION_RAW_VALUE: u8 = OperationType::MNT6PAIR as u8;
+        result
+    }};
+}
+
+// After the curve coefficients and group order, G1 multiexp input has a
+// single trailing byte giving the number of (point, scalar) pairs that
+// follow. G1 coefficients are base-field (`Fp`) elements, `modulus_byte_len`
+// bytes each, which is the layout `decode_utils::skip_curve_coefficients_and_order`
+// re-derives.
+//
+// Deliberately narrow and standalone: this is a re-parse of the G1 layout,
+// not a call into `PublicG1Api`'s real point decoder, so it can disagree with
+// `perform_operation` on the same input if that layout ever changes — treat
+// the count as a best-effort estimate, not a validity proof. It covers only
+// `G1MULTIEXP`; G2's coefficients are extension-field elements with a wider,
+// differently-shaped encoding that nothing here re-derives, and pairing ops
+// (`BLS12PAIR`/`BNPAIR`/`MNT4PAIR`/`MNT6PAIR`) have curve-family-specific
+// twist/loop-count parameters between the order and the pair count that only
+// `PairingApiImplementation` knows how to skip. Neither is in scope for this
+// function; there is no general "pair count for any multiexp/pairing
+// operation" API.
+pub fn estimate_g1_multiexp_pair_count(input: &[u8]) -> Result<usize, ApiError> {
+    let (modulus_byte_len, _, _, rest_after_modulus) = decode_modulus_front_matter(input)?;
+
+    estimate_g1_multiexp_pair_count_after_modulus(modulus_byte_len, rest_after_modulus)
+}
+
+// Does the actual work of `estimate_g1_multiexp_pair_count`, split out so
+// tests can drive it with a hand-built `rest_after_modulus` instead of a full,
+// validly-encoded modulus.
+fn estimate_g1_multiexp_pair_count_after_modulus(modulus_byte_len: usize, rest_after_modulus: &[u8]) -> Result<usize, ApiError> {
+    use crate::public_interface::decode_utils::skip_curve_coefficients_and_order;
+
+    let (order_byte_len, after_order) = skip_curve_coefficients_and_order(modulus_byte_len, rest_after_modulus)?;
+    let (num_pairs, after_count) = after_order.split_first()
+        .ok_or_else(|| ApiError::InputIsTooShort("not enough bytes for the pair count".into()))?;
+    let num_pairs = *num_pairs as usize;
+
+    // Each pair is a G1 point (two `modulus_byte_len` Fp coordinates) followed
+    // by a scalar encoded to the same width as the group order. A pair-count
+    // byte alone doesn't prove the pairs are actually there, so check the
+    // declared count against what's left rather than trusting it blindly.
+    let pair_byte_len = 2 * modulus_byte_len + order_byte_len;
+    let expected_len = num_pairs.checked_mul(pair_byte_len)
+        .ok_or_else(|| ApiError::InputIsTooShort("declared pair count overflows input size".into()))?;
+    if after_count.len() < expected_len {
+        return Err(ApiError::InputIsTooShort("not enough bytes for the declared number of multiexp pairs".into()));
+    }
+
+    Ok(num_pairs)
+}
+
+// `OperationType`, the `*_OPERATION_RAW_VALUE` constants, `decode_operation_type`
+// and `dispatch_operation` are all generated from the single table in
+// `instructions.in` by `build.rs`, so adding a new operation never requires
+// touching more than one line.
+include!(concat!(env!("OUT_DIR"), "/operations_generated.rs"));
+
+// The hand-written table this crate used before the `instructions.in`/`build.rs`
+// generation had a typo'd name for this constant. The generated table fixes it,
+// but that breaks any existing Rust caller still referring to the old name, so
+// the old name is kept as a deprecated alias rather than dropped.
+#[deprecated(note = "renamed to BNPAIR_OPERATION_RAW_VALUE (this was a typo); will be removed in a later release")]
+pub const BNPAI_OPERATION_RAW_VALUE: u8 = BNPAIR_OPERATION_RAW_VALUE;
 
 // This is pure rust API
 pub fn perform_operation(operation: OperationType, input: &[u8]) -> Result<Vec<u8>, ApiError> {
-    match operation {
-        OperationType::G1ADD => {
-            PublicG1Api::add_points(&input)
-        },
-        OperationType::G1MUL => {
-            PublicG1Api::mul_point(&input)
-        },
-        OperationType::G1MULTIEXP => {
-            PublicG1Api::multiexp(&input)
-        },
-        OperationType::G2ADD => {
-            PublicG2Api::add_points(&input)
-        },
-        OperationType::G2MUL => {
-            PublicG2Api::mul_point(&input)
-        },
-        OperationType::G2MULTIEXP => {
-            PublicG2Api::multiexp(&input)
-        },
-        OperationType::BLS12PAIR | OperationType::BNPAIR | OperationType::MNT4PAIR | OperationType::MNT6PAIR => {
-            use crate::field::*;
-            use crate::public_interface::decode_utils::*;
-
-            let modulus_limbs = {
-                let (_, modulus, _) = parse_modulus_and_length(&input)?;
-                let modulus_limbs = num_limbs_for_modulus(&modulus)?;
-
-                modulus_limbs
-            };
-
-            match operation {
-                OperationType::BLS12PAIR => {
-                    let result: Result<Vec<u8>, ApiError> = expand_for_modulus_limbs!(modulus_limbs, PairingApiImplementation, input, pair_bls12); 
-
-                    result
-                },
-                OperationType::BNPAIR => {
-                    let result: Result<Vec<u8>, ApiError> = expand_for_modulus_limbs!(modulus_limbs, PairingApiImplementation, input, pair_bn); 
-
-                    result
-                },
-                OperationType::MNT4PAIR => {
-                    let result: Result<Vec<u8>, ApiError> = expand_for_modulus_limbs!(modulus_limbs, PairingApiImplementation, input, pair_mnt4); 
-
-                    result
-                },
-                OperationType::MNT6PAIR => {
-                    let result: Result<Vec<u8>, ApiError> = expand_for_modulus_limbs!(modulus_limbs, PairingApiImplementation, input, pair_mnt6); 
-
-                    result
-                },
-
-                _ => {
-                    unreachable!()
-                }
-            }
-        }
-    }
+    dispatch_operation(operation, input)
 }
 
-// this is C interface
-#[no_mangle]
-pub extern "C" fn c_perform_operation(
-    op: ::std::os::raw::c_char,
-    i: *const ::std::os::raw::c_char,
-    i_len: u32,
-    o: *mut ::std::os::raw::c_char,
-    o_len: *mut u32,
-    err: *mut ::std::os::raw::c_char,
-    char_len: *mut u32) -> u32 
-{            
-    use std::io::Write;
-
-    let op_u8: u8 = unsafe { std::mem::transmute(op) };
-    let err_out_i8: &mut [i8] = unsafe { std::slice::from_raw_parts_mut(err, PREALLOCATE_FOR_ERROR_BYTES) };
-    let mut err_out: &mut [u8] = unsafe { std::mem::transmute(err_out_i8) };
-
-    let operation = match op_u8 {
-        G1ADD_OPERATION_RAW_VALUE => {
-            OperationType::G1ADD
-        },
-        G1MUL_OPERATION_RAW_VALUE => {
-            OperationType::G1MUL
-        },
-        G1MULTIEXP_OPERATION_RAW_VALUE => {
-            OperationType::G1MULTIEXP
-        },
-        G2ADD_OPERATION_RAW_VALUE => {
-            OperationType::G2ADD
-        },
-        G2MUL_OPERATION_RAW_VALUE => {
-            OperationType::G2MUL
-        },
-        G2MULTIEXP_OPERATION_RAW_VALUE => {
-            OperationType::G2MULTIEXP
-        },
-        BLS12PAIR_OPERATION_RAW_VALUE => {
-            OperationType::BLS12PAIR
-        },
-        BNPAI_OPERATION_RAW_VALUE => {
-            OperationType::BNPAIR
-        },
-        MNT4PAIR_OPERATION_RAW_VALUE => {
-            OperationType::MNT4PAIR
-        },
-        MNT6PAIR_OPERATION_RAW_VALUE => {
-            OperationType::MNT6PAIR
-        },
-        _ => {
-            let written = err_out.write(b"Unknown operation type\0");
-            if let Ok(bytes_written) = written {
-                unsafe { *char_len = bytes_written as u32 };
-            } else {
-                unsafe { *char_len = 0u32 };
-            }
-
-            return 1u32;
-        }
-    };
-
-    let input_i8: & [i8] = unsafe { std::slice::from_raw_parts(i, i_len as usize) };
-    let input: &[u8] = unsafe { std::mem::transmute(input_i8) };
-
-    let raw_out_i8: &mut [i8] = unsafe { std::slice::from_raw_parts_mut(o, PREALLOCATE_FOR_ERROR_BYTES) };
-    let mut raw_out: &mut [u8] = unsafe { std::mem::transmute(raw_out_i8) };
-
-    let result = perform_operation(operation, input);
-
-    match result {
-        Ok(result) => {
-            let written = raw_out.write(result.as_ref());
-            if let Ok(bytes_written) = written {
-                unsafe { *o_len = bytes_written as u32 };
-                return 0u32;
-            }
-
-            let written = err_out.write(b"Failed to write the result\0");
-            if let Ok(bytes_written) = written {
-                unsafe { *char_len = bytes_written as u32 };
-            } else {
-                unsafe { *char_len = 0u32 };
-            }
-
-            return 1u32;
-        },
-        Err(error) => {
-            use std::error::Error;
-
-            let err_description = error.description();
-            let written = err_out.write(err_description.as_bytes());
-            if let Ok(bytes_written) = written {
-                unsafe { *char_len = bytes_written as u32 };
-            } else {
-                unsafe { *char_len = 0u32 };
-            }
-
-            return 1u32;
-        }
+// Runs a batch of operations sequentially, built on top of `perform_operation`.
+// A failing item does not abort the batch: every item gets its own `Result`, so
+// callers (e.g. a benchmark driving many pairings in a loop, or a host validating
+// a batch of proofs) can see exactly which items failed and why.
+pub fn perform_operations(operations: &[(OperationType, &[u8])]) -> Vec<Result<Vec<u8>, ApiError>> {
+    operations.iter().map(|(operation, input)| perform_operation(*operation, input)).collect()
+}
+
+// Cheap, parse-only view of an operation's modulus front matter. Built from
+// the same parse `dispatch_operation` uses to pick a limb-width
+// specialization, so a host can reject malformed precompile input, or
+// estimate cost from modulus size, without ever reaching the (much more
+// expensive) arithmetic. This covers every operation identically, since every
+// operation's input starts with the same modulus encoding — it does not
+// extract curve family parameters (coefficients, twist type, and similar),
+// which are curve- and operation-specific and would need the real per-curve
+// decoders (`PublicG1Api`/`PublicG2Api`/`PairingApiImplementation`) wired in to
+// extract generically; that's a known gap, not a silent omission, and there
+// are no plans to close it here short of those decoders existing.
+//
+// A G1 multiexp pair count is available separately through
+// `estimate_g1_multiexp_pair_count`, not as a field here: it can disagree with
+// `perform_operation` on the same input (see that function's doc), which is a
+// different reliability class than the fields below, and it has no analogue
+// for pairing operations or G2 multiexp, so folding it into a field that's
+// `Option` for every other operation would overstate how generally it applies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OperationDescriptor {
+    pub operation: OperationType,
+    pub modulus_byte_len: usize,
+    // The modulus's actual bit length, not just `modulus_byte_len * 8`: a
+    // modulus can use as few as one bit of its top byte (e.g. a 249-bit
+    // modulus still has a 32-byte encoding), so the byte length alone
+    // overstates the true bit length by up to 7 bits. Callers comparing
+    // against a target security level need this field, not the byte length.
+    pub modulus_bit_length: usize,
+    pub modulus_limbs: usize,
+}
+
+pub fn decode_operation(operation: OperationType, input: &[u8]) -> Result<OperationDescriptor, ApiError> {
+    let (modulus_byte_len, modulus_bit_length, modulus_limbs, _) = decode_modulus_front_matter(input)?;
+
+    Ok(OperationDescriptor {
+        operation,
+        modulus_byte_len,
+        modulus_bit_length,
+        modulus_limbs,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // [A (modulus_byte_len) | B (modulus_byte_len) | order_len | order (order_len) | num_pairs | ...]
+    fn multiexp_tail(modulus_byte_len: usize, order_byte_len: usize, num_pairs: u8) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend(core::iter::repeat(0u8).take(2 * modulus_byte_len));
+        bytes.push(order_byte_len as u8);
+        bytes.extend(core::iter::repeat(0u8).take(order_byte_len));
+        bytes.push(num_pairs);
+
+        bytes
     }
-} 
\ No newline at end of file
+
+    // Appends `num_pairs` worth of (point, scalar) tuples after the count byte,
+    // each a `2 * modulus_byte_len + order_byte_len` byte-wide placeholder.
+    fn multiexp_tail_with_pairs(modulus_byte_len: usize, order_byte_len: usize, num_pairs: u8) -> Vec<u8> {
+        let mut bytes = multiexp_tail(modulus_byte_len, order_byte_len, num_pairs);
+        let pair_byte_len = 2 * modulus_byte_len + order_byte_len;
+        bytes.extend(core::iter::repeat(0u8).take(num_pairs as usize * pair_byte_len));
+
+        bytes
+    }
+
+    #[test]
+    fn estimate_g1_multiexp_pair_count_after_modulus_reads_trailing_count_byte() {
+        let rest = multiexp_tail_with_pairs(32, 32, 7);
+
+        assert_eq!(estimate_g1_multiexp_pair_count_after_modulus(32, &rest).unwrap(), 7);
+    }
+
+    #[test]
+    fn estimate_g1_multiexp_pair_count_after_modulus_rejects_truncated_curve_coefficients() {
+        // Exactly `2 * modulus_byte_len` bytes: no room for even the order-length byte.
+        let rest = vec![0u8; 64];
+
+        assert!(estimate_g1_multiexp_pair_count_after_modulus(32, &rest).is_err());
+    }
+
+    #[test]
+    fn estimate_g1_multiexp_pair_count_after_modulus_rejects_truncated_order() {
+        // Order length byte says 32 bytes follow, but only 10 are present, with no
+        // room left over for the trailing pair-count byte either.
+        let mut rest = vec![0u8; 64];
+        rest.push(32);
+        rest.extend(core::iter::repeat(0u8).take(10));
+
+        assert!(estimate_g1_multiexp_pair_count_after_modulus(32, &rest).is_err());
+    }
+
+    #[test]
+    fn estimate_g1_multiexp_pair_count_after_modulus_rejects_missing_count_byte() {
+        // Curve coefficients and order are exactly present, but the pair-count byte
+        // that should follow is missing.
+        let mut rest = multiexp_tail(32, 32, 5);
+        rest.pop();
+
+        assert!(estimate_g1_multiexp_pair_count_after_modulus(32, &rest).is_err());
+    }
+
+    #[test]
+    fn estimate_g1_multiexp_pair_count_after_modulus_rejects_missing_pair_bytes() {
+        // The count byte claims 7 pairs, but none of the (point, scalar) bytes
+        // that should follow are actually present.
+        let rest = multiexp_tail(32, 32, 7);
+
+        assert!(estimate_g1_multiexp_pair_count_after_modulus(32, &rest).is_err());
+    }
+
+    #[test]
+    fn estimate_g1_multiexp_pair_count_after_modulus_rejects_partial_last_pair() {
+        // 3 full pairs are declared, but the input is one byte short of the
+        // third pair's scalar.
+        let mut rest = multiexp_tail_with_pairs(32, 32, 3);
+        rest.pop();
+
+        assert!(estimate_g1_multiexp_pair_count_after_modulus(32, &rest).is_err());
+    }
+}